@@ -0,0 +1,90 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+use varivari::io::VarIntReadExt;
+use varivari::nom::varint;
+use varivari::VarInt;
+
+// One-byte values, boundary values at each 7-bit step, 25565 (3 bytes) and a full
+// 5-byte value, so a regression in `find`/`find_from_loose` at any length shows up in at least one group.
+const VALUES: &[(&str, i32)] = &[
+    ("1_byte", 1),
+    ("boundary_7", 0x7F),
+    ("boundary_14", 0x3FFF),
+    ("boundary_21", 0x1F_FFFF),
+    ("boundary_28", 0x0FFF_FFFF),
+    ("3_byte_25565", 25565),
+    ("5_byte_i32_min", i32::MIN),
+];
+
+fn bench_encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode/from_i32");
+    for &(name, value) in VALUES {
+        group.bench_function(name, |b| b.iter(|| VarInt::from(black_box(value))));
+    }
+    group.finish();
+}
+
+fn bench_decode_try_from(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode/try_from_slice");
+    for &(name, value) in VALUES {
+        let encoded = VarInt::from(value);
+        let bytes = encoded.as_slice();
+        group.bench_function(name, |b| b.iter(|| VarInt::try_from(black_box(bytes))));
+    }
+    group.finish();
+}
+
+fn bench_decode_nom(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode/nom_varint");
+    for &(name, value) in VALUES {
+        let encoded = VarInt::from(value);
+        let bytes = encoded.as_slice();
+        group.bench_function(name, |b| b.iter(|| varint(black_box(bytes))));
+    }
+    group.finish();
+}
+
+fn bench_read_varint(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode/read_varint");
+    for &(name, value) in VALUES {
+        let encoded = VarInt::from(value);
+        let bytes = encoded.as_slice();
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || bytes,
+                |mut r| r.read_varint().unwrap(),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+// Simulates a real packet stream: a buffer of many concatenated varints of mixed length.
+fn bench_decode_stream(c: &mut Criterion) {
+    let mut buf = Vec::new();
+    for i in 0..4096i32 {
+        buf.extend_from_slice(VarInt::from(i * 2003).as_slice());
+    }
+
+    c.bench_function("decode/mixed_length_stream", |b| {
+        b.iter(|| {
+            let mut rest: &[u8] = &buf;
+            while !rest.is_empty() {
+                let (tail, value) = varint(rest).unwrap();
+                black_box(value);
+                rest = tail;
+            }
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_encode,
+    bench_decode_try_from,
+    bench_decode_nom,
+    bench_read_varint,
+    bench_decode_stream,
+);
+criterion_main!(benches);
@@ -0,0 +1,22 @@
+//! Error types returned by the fallible [`VarInt`](crate::VarInt) and
+//! [`VarLong`](crate::VarLong) conversions.
+
+/// Returned by `TryFrom<VarIntInner>` when the array contains no byte with a clear MSB
+/// within [`VarInt::MAX_LEN`](crate::VarInt::MAX_LEN) bytes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TryFromVarIntInnerError(pub(crate) ());
+
+/// Returned by `TryFrom<&[u8]>` when the slice contains no byte with a clear MSB within
+/// [`VarInt::MAX_LEN`](crate::VarInt::MAX_LEN) bytes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TryFromVarIntSliceError(pub(crate) ());
+
+/// Returned by `TryFrom<VarLongInner>` when the array contains no byte with a clear MSB
+/// within [`VarLong::MAX_LEN`](crate::VarLong::MAX_LEN) bytes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TryFromVarLongInnerError(pub(crate) ());
+
+/// Returned by `TryFrom<&[u8]>` when the slice contains no byte with a clear MSB within
+/// [`VarLong::MAX_LEN`](crate::VarLong::MAX_LEN) bytes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TryFromVarLongSliceError(pub(crate) ());
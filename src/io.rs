@@ -1,3 +1,58 @@
+#[cfg(any(feature = "std", feature = "tokio"))]
+use std::io;
+
+#[cfg(any(feature = "std", feature = "tokio"))]
+use crate::{
+    LooseVarInt, LooseVarLong, VarInt, VarIntFindResult, VarIntInner, VarLong,
+    VarLongFindResult, VarLongInner, MSB,
+};
+
+// Shared by the sync and async read_varint/read_varlong implementations so they can't
+// drift apart: both read the same byte-by-byte `buf`/`len` (returning UnexpectedEof
+// themselves on a premature end) and hand it here to do the too-long/overlong
+// bookkeeping exactly once.
+#[cfg(any(feature = "std", feature = "tokio"))]
+fn finish_varint(mut buf: VarIntInner, mut len: usize) -> io::Result<VarInt> {
+    if len == 0 {
+        return Err(io::ErrorKind::InvalidData.into());
+    }
+
+    match VarInt::find_from_loose(unsafe { LooseVarInt::from_unchecked(&buf[..len]) }) {
+        VarIntFindResult::Tight(..) => {}
+        VarIntFindResult::Loose(_, actual_len) => {
+            buf[actual_len - 1] &= !MSB;
+            buf[actual_len..len].fill(0);
+            len = actual_len;
+        }
+    }
+
+    Ok(VarInt {
+        inner: buf,
+        len: len as u8,
+    })
+}
+
+#[cfg(any(feature = "std", feature = "tokio"))]
+fn finish_varlong(mut buf: VarLongInner, mut len: usize) -> io::Result<VarLong> {
+    if len == 0 {
+        return Err(io::ErrorKind::InvalidData.into());
+    }
+
+    match VarLong::find_from_loose(unsafe { LooseVarLong::from_unchecked(&buf[..len]) }) {
+        VarLongFindResult::Tight(..) => {}
+        VarLongFindResult::Loose(_, actual_len) => {
+            buf[actual_len - 1] &= !MSB;
+            buf[actual_len..len].fill(0);
+            len = actual_len;
+        }
+    }
+
+    Ok(VarLong {
+        inner: buf,
+        len: len as u8,
+    })
+}
+
 #[cfg(feature = "std")]
 pub use std_io::*;
 #[cfg(feature = "std")]
@@ -5,7 +60,8 @@ mod std_io {
     use core::slice;
     use std::io::{self, Read, Write};
 
-    use crate::{LooseVarInt, VarInt, VarIntFindResult::*, VarIntInner, MSB};
+    use super::{finish_varint, finish_varlong};
+    use crate::{VarInt, VarIntInner, VarLong, VarLongInner, MSB};
 
     // r4: impl Read?
     pub trait VarIntReadExt: Read {
@@ -39,23 +95,31 @@ mod std_io {
                 }
             }
 
-            if len == 0 {
-                return Err(io::ErrorKind::InvalidData.into());
-            }
+            finish_varint(buf, len)
+        }
 
-            match VarInt::find_from_loose(LooseVarInt(&buf[..len])) {
-                Tight(..) => {}
-                Loose(_, actual_len) => {
-                    buf[actual_len - 1] &= MSB;
-                    buf[actual_len..len].fill(0);
-                    len = actual_len;
+        fn read_varlong(&mut self) -> io::Result<VarLong> {
+            let mut buf: VarLongInner = [0; 10];
+
+            let mut len = 0;
+            for (idx, byte) in buf.iter_mut().enumerate() {
+                match self.read(slice::from_mut(byte))? {
+                    1 => {
+                        if *byte & MSB == 0 {
+                            len = idx + 1;
+                            break;
+                        }
+                    }
+                    0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+                    _ => unreachable!(concat!(
+                        "This is a bug of ",
+                        env!("CARGO_PKG_REPOSITORY"),
+                        ". Please create an issue to report it."
+                    )),
                 }
             }
 
-            Ok(VarInt {
-                inner: buf,
-                len: len as u8,
-            })
+            finish_varlong(buf, len)
         }
     }
     impl<R: Read> VarIntReadExt for R {}
@@ -65,6 +129,10 @@ mod std_io {
         fn write_varint(&mut self, source: &VarInt) -> io::Result<()> {
             self.write_all(source.as_ref())
         }
+
+        fn write_varlong(&mut self, source: &VarLong) -> io::Result<()> {
+            self.write_all(source.as_ref())
+        }
     }
     impl<W: Write> VarIntWriteExt for W {}
 }
@@ -79,7 +147,8 @@ mod tokio_io {
     use std::io;
     use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
-    use crate::{LooseVarInt, VarInt, VarIntFindResult::*, VarIntInner, MSB};
+    use super::{finish_varint, finish_varlong};
+    use crate::{VarInt, VarIntInner, VarLong, VarLongInner, MSB};
 
     // r4: impl Read?
     #[async_trait]
@@ -117,23 +186,34 @@ mod tokio_io {
                 }
             }
 
-            if len == 0 {
-                return Err(io::ErrorKind::InvalidData.into());
-            }
+            finish_varint(buf, len)
+        }
+
+        async fn read_varlong(&mut self) -> io::Result<VarLong>
+        where
+            Self: Unpin,
+        {
+            let mut buf: VarLongInner = [0; 10];
 
-            match VarInt::find_from_loose(unsafe { LooseVarInt::from_unchecked(&buf[..len]) }) {
-                Tight(..) => {}
-                Loose(_, actual_len) => {
-                    buf[actual_len - 1] &= MSB;
-                    buf[actual_len..len].fill(0);
-                    len = actual_len;
+            let mut len = 0;
+            for (idx, byte) in buf.iter_mut().enumerate() {
+                match self.read(slice::from_mut(byte)).await? {
+                    1 => {
+                        if *byte & MSB == 0 {
+                            len = idx + 1;
+                            break;
+                        }
+                    }
+                    0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+                    _ => unreachable!(concat!(
+                        "This is a bug of ",
+                        env!("CARGO_PKG_REPOSITORY"),
+                        ". Please create an issue to report it."
+                    )),
                 }
             }
 
-            Ok(VarInt {
-                inner: buf,
-                len: len as u8,
-            })
+            finish_varlong(buf, len)
         }
     }
     impl<R: AsyncRead> VarIntAsyncReadExt for R {}
@@ -147,6 +227,13 @@ mod tokio_io {
         {
             self.write_all(source.as_ref()).await
         }
+
+        async fn write_varlong(&mut self, source: &VarLong) -> io::Result<()>
+        where
+            Self: Unpin,
+        {
+            self.write_all(source.as_ref()).await
+        }
     }
     impl<W: AsyncWrite> VarIntAsyncWriteExt for W {}
 }
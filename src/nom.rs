@@ -1,8 +1,6 @@
-use nom::bytes::complete::take;
-use nom::{Err, IResult, Needed};
-use std::error::Error;
+use nom::{Err, IResult};
 
-use crate::{LooseVarInt, VarInt, VarIntFindResult, VarIntInner, MSB};
+use crate::{LooseVarInt, LooseVarLong, VarInt, VarLong, MSB};
 
 macro_rules! ignore {
     ($($tt:tt)*) => {};
@@ -20,7 +18,7 @@ ignore! {
     }
 }
 
-pub fn varint_loose(input: &[u8]) -> IResult<&[u8], LooseVarInt, ()> {
+pub fn varint_loose(input: &[u8]) -> IResult<&[u8], LooseVarInt<'_>, ()> {
     let Some((idx, _)) =
         input
             .iter()
@@ -31,11 +29,39 @@ pub fn varint_loose(input: &[u8]) -> IResult<&[u8], LooseVarInt, ()> {
             return Err(Err::Error(()))
         };
 
-    Ok((&input[idx + 1..], LooseVarInt(&input[..=idx])))
+    // SAFETY: `idx` is the index of the first byte with a clear MSB, found within
+    // `VarInt::MAX_LEN` bytes.
+    Ok((
+        &input[idx + 1..],
+        unsafe { LooseVarInt::from_unchecked(&input[..=idx]) },
+    ))
 }
 
 pub fn varint(input: &[u8]) -> IResult<&[u8], VarInt, ()> {
-    varint_loose(input).map(|(i, loose)| (i, loose.to_varint()))
+    varint_loose(input).map(|(i, loose)| (i, VarInt::from_loose(loose)))
+}
+
+pub fn varlong_loose(input: &[u8]) -> IResult<&[u8], LooseVarLong<'_>, ()> {
+    let Some((idx, _)) =
+        input
+            .iter()
+            .enumerate()
+            .take(VarLong::MAX_LEN)
+            .find(|(_, &byte)| byte & MSB == 0)
+        else {
+            return Err(Err::Error(()))
+        };
+
+    // SAFETY: `idx` is the index of the first byte with a clear MSB, found within
+    // `VarLong::MAX_LEN` bytes.
+    Ok((
+        &input[idx + 1..],
+        unsafe { LooseVarLong::from_unchecked(&input[..=idx]) },
+    ))
+}
+
+pub fn varlong(input: &[u8]) -> IResult<&[u8], VarLong, ()> {
+    varlong_loose(input).map(|(i, loose)| (i, VarLong::from_loose(loose)))
 }
 
 ignore!(
@@ -0,0 +1,78 @@
+//! Continuation-bit scanning and loose/tight overlong detection shared by
+//! [`VarInt`](crate::VarInt) and [`VarLong`](crate::VarLong), which differ only in their
+//! maximum encoded length (`VarInt::MAX_LEN` vs `VarLong::MAX_LEN`).
+
+pub(crate) const MSB: u8 = 0b1000_0000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FindResult<'a> {
+    Tight(&'a [u8]),
+    Loose(&'a [u8], usize),
+}
+
+/// A byte slice that ends on the first byte with a clear MSB within `1..=max_len` bytes
+/// for whichever `max_len` produced it — i.e. a structurally valid (but possibly
+/// overlong) varint/varlong encoding.
+#[derive(Debug, Copy, Clone)]
+pub struct Loose<'a>(&'a [u8]);
+impl<'a> Loose<'a> {
+    pub const fn inner(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// # Safety
+    /// `slice` must be non-empty and its last byte must be the first one (reading from
+    /// the front) whose MSB is clear.
+    pub unsafe fn from_unchecked(slice: &'a [u8]) -> Loose<'a> {
+        Loose(slice)
+    }
+}
+impl<'a> AsRef<[u8]> for Loose<'a> {
+    fn as_ref(&self) -> &[u8] {
+        self.0
+    }
+}
+
+#[inline]
+pub(crate) fn find_loose(slice: &[u8], max_len: usize) -> Option<Loose<'_>> {
+    let (idx, _) = slice
+        .iter()
+        .enumerate()
+        .take(max_len) // None if MSB is in slice[max_len..]
+        .find(|(_, &byte)| byte & MSB == 0)?; // None if the MSB of the slice is all 1s
+
+    Some(unsafe {
+        // SAFETY: `Iterator::take` and returning (by `?`) if the result is None ensures
+        // that `idx + 1` is contained in 1..=max_len, and `find` stops on the first byte
+        // with a clear MSB.
+        Loose::from_unchecked(&slice[..=idx])
+    })
+}
+
+#[inline]
+pub(crate) fn find_from_loose(loose: Loose) -> FindResult {
+    use FindResult::*;
+
+    let slice = loose.as_ref();
+
+    // SAFETY: `Loose`'s invariant guarantees `slice` is non-empty.
+    if slice.len() == 1 || unsafe { slice.last().unwrap_unchecked() } & !MSB != 0 {
+        return Tight(loose.0);
+    }
+
+    let len = slice
+        .iter()
+        .enumerate()
+        .rev()
+        .skip(1) // because it's checked above not to be tight
+        .find(|(_, &byte)| byte & !MSB != 0)
+        .map(|(idx, _)| idx + 1)
+        .unwrap_or(1); // not unwrap_unchecked because it might be 0 of any (1..=max_len) length
+
+    Loose(loose.0, len)
+}
+
+#[inline]
+pub(crate) fn find(slice: &[u8], max_len: usize) -> Option<FindResult<'_>> {
+    find_loose(slice, max_len).map(find_from_loose)
+}
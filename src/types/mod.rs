@@ -0,0 +1,7 @@
+mod common;
+mod varint;
+mod varlong;
+
+pub(crate) use common::MSB;
+pub use varint::{LooseVarInt, VarInt, VarIntFindResult, VarIntInner};
+pub use varlong::{LooseVarLong, VarLong, VarLongFindResult, VarLongInner};
@@ -1,229 +1,308 @@
-/*
-Design Philosophy (kinda)
-
-r1:     i32 -> VarInt
-r2: [u8; 5] -> VarInt?
-r3:   &[u8] -> VarInt?
-r4: impl Read?
-r5: impl AsyncRead?
-
-w1: VarInt -> i32
-w2: VarInt -> [u8; 5]
-w3: VarInt ->&[u8; 5]
-w4: VarInt ->&[u8]
-w5: impl Write
-w6: impl AsyncWrite
-*/
-
-//! MCMODERN's variable-length integers are fairly tricky to *properly* decode.
-//!
-//! varivari aims to provide the most ergonomic APIs to handle [`VarInt`]s by making sure that the following conversions are always possible.
-//! ```
-#![doc = concat!("# use ", module_path!(), "::{VarInt, VarIntInner};")]
-//! # macro_rules! ascr {
-//! #     ($expr:expr => $ty:ty) => {{
-//! #         let tmp: $ty = $expr;
-//! #         tmp
-//! #     }}
-//! # }
-//! // Suppose we have all these:
-//! const I32: i32 = 25565;
-//! const BIN: i32 = 0b0000_0000000_0000001_1000111_1011101;
-//! const ARR: [u8; 5] = [0b1101_1101, 0b1100_0111, 0b0000_0001, 0, 0];
-//! assert_eq!(I32, BIN);
-//!
-//! // r1, w1: seamlessly convert between VarInt and i32
-//! let foo = VarInt::from(I32);
-//! let bar = i32::from(foo);
-//! assert_eq!(I32, bar);
-//!
-//! // r2, r3, w2: extract or do a checked conversion from [u8; VarInt::MAX_LEN] (type-aliased as VarIntInner) or &[u8] to VarInt
-//! let foo = VarInt::try_from(ARR.clone()).unwrap();
-//! let bar = VarInt::try_from(&ARR[..3]).unwrap();
-//! let qux = VarIntInner::from(foo.clone());
-//! assert_eq!(BIN, i32::from(foo));
-//! assert_eq!(BIN, i32::from(bar));
-//! assert_eq!(ARR, qux);
-//!
-//! // w3, w4: AsRef<[u8]>, AsRef<VarIntInner>
-//! let foo = VarInt::try_from(ARR.clone()).unwrap();
-//! assert_eq!(&ARR, ascr!( foo.as_ref() => &[u8] ));
-//! assert_eq!(&ARR[..3], ascr!( foo.as_ref() => &[u8] ));
-//!
-//! // r4, w5: VarIntReadExt: Read; VarIntWriteExt: Write;
-//! // r5, w6: VarIntAsyncReadExt: AsyncRead; VarIntAsyncWriteExt: AsyncWrite;
-//! ```
-
-use async_trait::async_trait;
-use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
-
-use std::cmp;
-use std::io::{self, Read, Write};
-
-const MSB: u8 = 0b1000_0000;
-
-pub enum VarIntFindResult<'a> {
-    Tight(&'a [u8]),
-    Loose(&'a [u8], usize),
-    Invalid,
-}
+use core::hint::unreachable_unchecked;
+
+use super::common::{self, FindResult};
+use crate::{TryFromVarIntInnerError, TryFromVarIntSliceError};
+
+const MSB: u8 = common::MSB;
+
+pub type VarIntFindResult<'a> = FindResult<'a>;
+pub type LooseVarInt<'a> = common::Loose<'a>;
 
 pub type VarIntInner = [u8; VarInt::MAX_LEN];
-#[repr(transparent)]
 #[derive(Debug, Clone)]
-pub struct VarInt(VarIntInner);
+pub struct VarInt {
+    pub(crate) inner: VarIntInner,
+    pub(crate) len: u8,
+}
+
 impl VarInt {
     // ideal but div_ceil() is unstable atm
     // pub const MAX_LEN: usize = i32::BITS.div_ceil(7) as usize;
     pub const MAX_LEN: usize = 5;
+    pub const LAST_BYTE_MASK: u8 = 0b0000_1111;
+    pub const fn len(&self) -> usize {
+        self.len as usize
+    }
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
 
-    #[inline]
-    fn find_loose(slice: &[u8]) -> Option<&[u8]> {
-        let (idx, _) = slice
-            .iter()
-            .enumerate()
-            .take(VarInt::MAX_LEN)
-            .find(|(_, &byte)| byte & MSB == MSB)?;
+    pub fn as_slice(&self) -> &[u8] {
+        &self.inner[..self.len()]
+    }
+    pub const fn as_inner(&self) -> &VarIntInner {
+        &self.inner
+    }
+    pub const fn into_inner(self) -> VarIntInner {
+        self.inner
+    }
 
-        Some(&slice[..=idx])
+    #[inline]
+    pub(crate) fn find_from_loose(loose: LooseVarInt) -> VarIntFindResult {
+        common::find_from_loose(loose)
     }
 
     #[inline]
-    fn find(slice: &[u8]) -> VarIntFindResult {
-        use VarIntFindResult::*;
+    pub(crate) fn find(slice: &[u8]) -> Option<VarIntFindResult<'_>> {
+        common::find(slice, VarInt::MAX_LEN)
+    }
 
-        let Some(slice) = VarInt::find_loose(slice) else {
-            return Invalid;
-        };
+    /// Builds a [`VarInt`] out of a loose (possibly overlong) slice, normalizing it to
+    /// its minimal encoding the same way [`TryFrom<&[u8]>`](VarInt::try_from) does.
+    pub fn from_loose(loose: LooseVarInt) -> VarInt {
+        use FindResult::*;
 
-        // SAFETY: `find_loose()` returns `None` and therefore `find()` returns `Invalid` at the above let-else, which makes it impossible for `silce` to be empty.
-        if unsafe { slice.last().unwrap_unchecked() } & !MSB != 0 {
-            return Tight(slice);
-        }
+        let mut inner: VarIntInner = [0; Self::MAX_LEN];
 
-        let Some((idx, _)) = slice
-            .iter()
-            .enumerate()
-            .rev()
-            .find(|(_, &byte)| byte & !MSB != 0) else {
-                return Invalid;
-            };
+        let len = match VarInt::find_from_loose(loose) {
+            Tight(slice) => {
+                inner[..slice.len()].copy_from_slice(slice);
+                slice.len()
+            }
+            Loose(slice, actual_len) => {
+                inner[..slice.len()].copy_from_slice(slice);
+                inner[actual_len - 1] &= !MSB;
+                inner[actual_len..slice.len()].fill(0);
+                actual_len
+            }
+        };
 
-        Loose(slice, idx + 1)
+        VarInt {
+            inner,
+            len: len as u8,
+        }
     }
 }
 
-// r1:     i32 -> VarInt
-impl From<i32> for VarInt {
-    fn from(source: i32) -> Self {
-        let mut source = source as u32;
+// r1: i32 -> VarInt
+impl From<u32> for VarInt {
+    fn from(mut source: u32) -> Self {
         let mut buf = [0u8; Self::MAX_LEN];
 
-        for byte in buf.iter_mut() {
+        for (idx, byte) in buf.iter_mut().enumerate() {
             *byte = source as u8 & !MSB;
             source >>= 7;
             if source == 0 {
-                break;
+                return VarInt {
+                    inner: buf,
+                    len: idx as u8 + 1,
+                };
             }
             *byte |= MSB
         }
 
-        VarInt(buf)
+        // SAFETY: `buf` always has 5 elements and the loop always breaks,
+        // because at 5th iteration, `source == 0` is the same as
+        // `(whatever_u32 >> 35) == 0` which is always true.
+        unsafe { unreachable_unchecked() }
+    }
+}
+impl From<i32> for VarInt {
+    fn from(source: i32) -> Self {
+        (source as u32).into()
     }
 }
 
 // r2: [u8; 5] -> VarInt?
 impl TryFrom<VarIntInner> for VarInt {
-    type Error = ();
-    fn try_from(_: VarIntInner) -> Result<Self, Self::Error> {
-        todo!()
+    type Error = TryFromVarIntInnerError;
+    fn try_from(mut source: VarIntInner) -> Result<Self, Self::Error> {
+        use FindResult::*;
+
+        let len = match VarInt::find(&source) {
+            None => return Err(TryFromVarIntInnerError(())),
+            Some(Tight(slice)) => slice.len(),
+            Some(Loose(_, actual_len)) => {
+                source[actual_len - 1] &= !MSB;
+                actual_len
+            }
+        };
+
+        source[len..].fill(0);
+
+        Ok(VarInt {
+            inner: source,
+            len: len as u8,
+        })
     }
 }
 
-// r3:   &[u8] -> VarInt?
+// r3: &[u8] -> VarInt?
 impl TryFrom<&[u8]> for VarInt {
-    type Error = ();
-    fn try_from(_: &[u8]) -> Result<Self, Self::Error> {
-        todo!()
-    }
-}
+    type Error = TryFromVarIntSliceError;
+    fn try_from(source: &[u8]) -> Result<Self, Self::Error> {
+        use FindResult::*;
 
-// r4: impl Read?
-pub trait VarIntReadExt: Read {
-    fn read_varint(&mut self) -> io::Result<VarInt> {
-        todo!()
-    }
-}
-impl<R: Read> VarIntReadExt for R {}
+        let mut buf: VarIntInner = [0; 5];
+
+        let (len, overlong) = match VarInt::find(source) {
+            None => return Err(TryFromVarIntSliceError(())),
+            Some(Tight(slice)) => (slice.len(), false),
+            Some(Loose(_, actual_len)) => (actual_len, true),
+        };
 
-// r5: impl AsyncRead?
-#[async_trait]
-pub trait VarIntAsyncReadExt: AsyncRead {
-    async fn read_varint(&mut self) -> io::Result<VarInt>
-    where
-        Self: Unpin,
-    {
-        todo!()
+        buf[..len].copy_from_slice(&source[..len]);
+        if overlong {
+            buf[len - 1] &= !MSB;
+        }
+
+        Ok(VarInt {
+            inner: buf,
+            len: len as u8,
+        })
     }
 }
-impl<R: AsyncRead> VarIntAsyncReadExt for R {}
 
 // w1: VarInt -> i32
-impl From<VarInt> for i32 {
+impl From<VarInt> for u32 {
+    #[inline]
     fn from(source: VarInt) -> Self {
-        // source
-        //     .0
-        //     .into_iter()
-        //     .enumerate()
-        //     .fold(0u32, |acc, (idx, byte)| acc | (byte as u32) << (idx * 7)) as Self
-
-        let mut result = 0u32;
+        let mut result = 0;
 
-        for (idx, byte) in source.0.into_iter().enumerate() {
-            result |= (byte as u32) << (idx * 7);
+        for (idx, byte) in source.inner.into_iter().enumerate() {
+            result |= ((byte & !MSB) as u32) << (idx * 7);
         }
 
-        result as Self
+        result
+    }
+}
+
+impl From<VarInt> for i32 {
+    #[inline]
+    fn from(source: VarInt) -> Self {
+        u32::from(source) as Self
     }
 }
 
 // w2: VarInt -> [u8; 5]
 impl From<VarInt> for VarIntInner {
     fn from(source: VarInt) -> Self {
-        source.0
+        source.inner
     }
 }
 
 // w3: VarInt ->&[u8; 5]
 impl AsRef<VarIntInner> for VarInt {
     fn as_ref(&self) -> &VarIntInner {
-        &self.0
+        &self.inner
     }
 }
 
 // w4: VarInt ->&[u8]
 impl AsRef<[u8]> for VarInt {
     fn as_ref(&self) -> &[u8] {
-        self.0.as_ref()
+        &self.inner[..self.len as usize]
     }
 }
 
-// w5: impl Write
-pub trait VarIntWriteExt: Write {
-    fn write_varint(&mut self, source: &VarInt) -> io::Result<()> {
-        self.write_all(source.as_ref())
+impl VarInt {
+    /// Decodes a [`VarInt`] from the front of `input` without the per-byte branch that
+    /// [`VarInt::find`] takes, for throughput-sensitive packet parsing.
+    ///
+    /// Loads up to 8 bytes of `input` as a little-endian word and locates the terminating
+    /// byte (the first one with a clear MSB) with a single masked `trailing_zeros` instead
+    /// of a per-byte loop, then gathers the 7-bit groups straight into the decoded value —
+    /// a single `pext` on `x86_64` with BMI2, or a shift-accumulate fallback elsewhere.
+    ///
+    /// Returns the decoded value and the number of bytes of `input` it consumed. The
+    /// consumed length always matches the loose (possibly overlong) encoding; the
+    /// returned [`VarInt`] is normalized to its minimal form, the same as
+    /// [`TryFrom<&[u8]>`](VarInt::try_from) produces for the same bytes.
+    pub fn decode_fast(input: &[u8]) -> Option<(VarInt, usize)> {
+        let n = input.len().min(8);
+        let mut padded = [0u8; 8];
+        padded[..n].copy_from_slice(&input[..n]);
+        let word = u64::from_le_bytes(padded);
+
+        let cont = word & 0x8080_8080_8080_8080;
+        let term = !cont & 0x8080_8080_8080_8080;
+        if term == 0 {
+            // no byte with a clear MSB among the first 8 -> can't be a valid VarInt
+            return None;
+        }
+
+        let len = (term.trailing_zeros() / 8) as usize + 1;
+        if len > VarInt::MAX_LEN || len > input.len() {
+            return None;
+        }
+
+        // Selects the low 7 bits of each of the first `len` bytes.
+        let group_mask = ((1u64 << (len * 8)) - 1) & 0x7F7F_7F7F_7F7F_7F7F;
+
+        #[cfg(all(target_arch = "x86_64", target_feature = "bmi2"))]
+        let value = unsafe { core::arch::x86_64::_pext_u64(word, group_mask) } as u32;
+
+        #[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+        let value = {
+            let bits = word & group_mask;
+            let mut value = 0u32;
+            for i in 0..len {
+                value |= (((bits >> (i * 8)) & 0x7F) as u32) << (i * 7);
+            }
+            value
+        };
+
+        // Re-deriving through `From<u32>` normalizes overlong encodings the same way
+        // `find_from_loose` does: trailing all-zero 7-bit groups contribute nothing to
+        // `value`, so its canonical encoding is always the shortest one for that value.
+        // The exception is a 5-byte encoding whose last byte carries bits above `u32`'s
+        // range: it is tight and `TryFrom<&[u8]>` keeps its bytes unchanged, so do the same.
+        if len == VarInt::MAX_LEN && input[len - 1] & !VarInt::LAST_BYTE_MASK != 0 {
+            let mut inner: VarIntInner = [0; VarInt::MAX_LEN];
+            inner.copy_from_slice(&input[..len]);
+            return Some((VarInt { inner, len: len as u8 }, len));
+        }
+
+        let varint = VarInt::from(value);
+        debug_assert!(varint.len() <= len);
+
+        Some((varint, len))
     }
 }
-impl<W: Write> VarIntWriteExt for W {}
 
-// w6: impl AsyncWrite
-#[async_trait]
-pub trait VarIntAsyncWriteExt: AsyncWrite {
-    async fn write_varint(&mut self, source: &VarInt) -> io::Result<()>
-    where
-        Self: Unpin,
-    {
-        self.write_all(source.as_ref()).await
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(value: i32) {
+        let varint = VarInt::from(value);
+        assert_eq!(value, i32::from(varint.clone()));
+
+        let (fast, len) = VarInt::decode_fast(varint.as_slice()).unwrap();
+        assert_eq!(len, varint.len());
+        assert_eq!(value, i32::from(fast));
+    }
+
+    #[test]
+    fn roundtrip_values() {
+        roundtrip(0);
+        roundtrip(1);
+        roundtrip(256);
+        roundtrip(16384);
+        roundtrip(25565);
+        roundtrip(i32::MIN);
+        roundtrip(i32::MAX);
+    }
+
+    #[test]
+    fn overlong_normalizes_to_shortest_encoding() {
+        // 1 encoded with two extra zero continuation bytes, instead of the tight [0x01].
+        let overlong = [0x81, 0x80, 0x00];
+        let varint = VarInt::try_from(&overlong[..]).unwrap();
+        assert_eq!(varint.as_slice(), &[0x01]);
+        assert_eq!(1, i32::from(varint));
+    }
+
+    #[test]
+    fn decode_fast_agrees_with_try_from_slice() {
+        for value in [0, 1, 127, 128, 256, 16384, 2097151, 2097152, i32::MIN, i32::MAX] {
+            let source = VarInt::from(value);
+
+            let (fast, fast_len) = VarInt::decode_fast(source.as_slice()).unwrap();
+            let slow = VarInt::try_from(source.as_slice()).unwrap();
+
+            assert_eq!(fast_len, source.len());
+            assert_eq!(fast.as_slice(), slow.as_slice());
+        }
     }
 }
-impl<W: AsyncWrite> VarIntAsyncWriteExt for W {}
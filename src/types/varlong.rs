@@ -0,0 +1,223 @@
+use core::hint::unreachable_unchecked;
+
+use super::common::{self, FindResult};
+use crate::{TryFromVarLongInnerError, TryFromVarLongSliceError};
+
+const MSB: u8 = common::MSB;
+
+pub type VarLongFindResult<'a> = FindResult<'a>;
+pub type LooseVarLong<'a> = common::Loose<'a>;
+
+pub type VarLongInner = [u8; VarLong::MAX_LEN];
+#[derive(Debug, Clone)]
+pub struct VarLong {
+    pub(crate) inner: VarLongInner,
+    pub(crate) len: u8,
+}
+
+impl VarLong {
+    pub const MAX_LEN: usize = 10;
+    pub const fn len(&self) -> usize {
+        self.len as usize
+    }
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.inner[..self.len()]
+    }
+    pub const fn as_inner(&self) -> &VarLongInner {
+        &self.inner
+    }
+    pub const fn into_inner(self) -> VarLongInner {
+        self.inner
+    }
+
+    #[inline]
+    pub(crate) fn find_from_loose(loose: LooseVarLong) -> VarLongFindResult {
+        common::find_from_loose(loose)
+    }
+
+    #[inline]
+    pub(crate) fn find(slice: &[u8]) -> Option<VarLongFindResult<'_>> {
+        common::find(slice, VarLong::MAX_LEN)
+    }
+
+    /// Builds a [`VarLong`] out of a loose (possibly overlong) slice, normalizing it to
+    /// its minimal encoding the same way [`TryFrom<&[u8]>`](VarLong::try_from) does.
+    pub fn from_loose(loose: LooseVarLong) -> VarLong {
+        use FindResult::*;
+
+        let mut inner: VarLongInner = [0; Self::MAX_LEN];
+
+        let len = match VarLong::find_from_loose(loose) {
+            Tight(slice) => {
+                inner[..slice.len()].copy_from_slice(slice);
+                slice.len()
+            }
+            Loose(slice, actual_len) => {
+                inner[..slice.len()].copy_from_slice(slice);
+                inner[actual_len - 1] &= !MSB;
+                inner[actual_len..slice.len()].fill(0);
+                actual_len
+            }
+        };
+
+        VarLong {
+            inner,
+            len: len as u8,
+        }
+    }
+}
+
+// r1: i64 -> VarLong
+impl From<u64> for VarLong {
+    fn from(mut source: u64) -> Self {
+        let mut buf = [0u8; Self::MAX_LEN];
+
+        for (idx, byte) in buf.iter_mut().enumerate() {
+            *byte = source as u8 & !MSB;
+            source >>= 7;
+            if source == 0 {
+                return VarLong {
+                    inner: buf,
+                    len: idx as u8 + 1,
+                };
+            }
+            *byte |= MSB
+        }
+
+        // SAFETY: `buf` always has 10 elements and the loop always breaks, because at
+        // the 10th iteration, `source == 0` is the same as `(whatever_u64 >> 70) == 0`
+        // which is always true.
+        unsafe { unreachable_unchecked() }
+    }
+}
+impl From<i64> for VarLong {
+    fn from(source: i64) -> Self {
+        (source as u64).into()
+    }
+}
+
+// r2: [u8; 10] -> VarLong?
+impl TryFrom<VarLongInner> for VarLong {
+    type Error = TryFromVarLongInnerError;
+    fn try_from(mut source: VarLongInner) -> Result<Self, Self::Error> {
+        use FindResult::*;
+
+        let len = match VarLong::find(&source) {
+            None => return Err(TryFromVarLongInnerError(())),
+            Some(Tight(slice)) => slice.len(),
+            Some(Loose(_, actual_len)) => {
+                source[actual_len - 1] &= !MSB;
+                actual_len
+            }
+        };
+
+        source[len..].fill(0);
+
+        Ok(VarLong {
+            inner: source,
+            len: len as u8,
+        })
+    }
+}
+
+// r3: &[u8] -> VarLong?
+impl TryFrom<&[u8]> for VarLong {
+    type Error = TryFromVarLongSliceError;
+    fn try_from(source: &[u8]) -> Result<Self, Self::Error> {
+        use FindResult::*;
+
+        let mut buf: VarLongInner = [0; Self::MAX_LEN];
+
+        let (len, overlong) = match VarLong::find(source) {
+            None => return Err(TryFromVarLongSliceError(())),
+            Some(Tight(slice)) => (slice.len(), false),
+            Some(Loose(_, actual_len)) => (actual_len, true),
+        };
+
+        buf[..len].copy_from_slice(&source[..len]);
+        if overlong {
+            buf[len - 1] &= !MSB;
+        }
+
+        Ok(VarLong {
+            inner: buf,
+            len: len as u8,
+        })
+    }
+}
+
+// w1: VarLong -> i64
+impl From<VarLong> for u64 {
+    #[inline]
+    fn from(source: VarLong) -> Self {
+        let mut result = 0;
+
+        for (idx, byte) in source.inner.into_iter().enumerate() {
+            result |= ((byte & !MSB) as u64) << (idx * 7);
+        }
+
+        result
+    }
+}
+
+impl From<VarLong> for i64 {
+    #[inline]
+    fn from(source: VarLong) -> Self {
+        u64::from(source) as Self
+    }
+}
+
+// w2: VarLong -> [u8; 10]
+impl From<VarLong> for VarLongInner {
+    fn from(source: VarLong) -> Self {
+        source.inner
+    }
+}
+
+// w3: VarLong ->&[u8; 10]
+impl AsRef<VarLongInner> for VarLong {
+    fn as_ref(&self) -> &VarLongInner {
+        &self.inner
+    }
+}
+
+// w4: VarLong ->&[u8]
+impl AsRef<[u8]> for VarLong {
+    fn as_ref(&self) -> &[u8] {
+        &self.inner[..self.len as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(value: i64) {
+        let varlong = VarLong::from(value);
+        assert_eq!(value, i64::from(varlong));
+    }
+
+    #[test]
+    fn roundtrip_values() {
+        roundtrip(0);
+        roundtrip(1);
+        roundtrip(256);
+        roundtrip(16384);
+        roundtrip(25565);
+        roundtrip(i64::MIN);
+        roundtrip(i64::MAX);
+    }
+
+    #[test]
+    fn overlong_normalizes_to_shortest_encoding() {
+        // 1 encoded with two extra zero continuation bytes, instead of the tight [0x01].
+        let overlong = [0x81, 0x80, 0x00];
+        let varlong = VarLong::try_from(&overlong[..]).unwrap();
+        assert_eq!(varlong.as_slice(), &[0x01]);
+        assert_eq!(1, i64::from(varlong));
+    }
+}